@@ -171,7 +171,11 @@ impl<'a> Benchmarker<'a> {
                 self.trip();
                 match self.start_test_orchestration(project, test, &logger) {
                     Ok(orchestration) => {
-                        for test_type in &test.urls {
+                        for test_type in test
+                            .urls
+                            .iter()
+                            .map(|(key, endpoint)| (key, &endpoint.path))
+                        {
                             logger.log(format!("Benchmarking: {}", test_type.0))?;
                             match self.run_benchmarks(&orchestration, &test_type, &logger) {
                                 Ok(results) => self.report_benchmark_success(
@@ -199,7 +203,11 @@ impl<'a> Benchmarker<'a> {
                         // We could not start this implementation's docker
                         // container(s); all of its test implementations must
                         // fail.
-                        for test_type in &test.urls {
+                        for test_type in test
+                            .urls
+                            .iter()
+                            .map(|(key, endpoint)| (key, &endpoint.path))
+                        {
                             self.report_benchmark_error(
                                 &mut benchmark_results,
                                 &test,
@@ -279,7 +287,11 @@ impl<'a> Benchmarker<'a> {
                     self.trip();
                     match self.start_test_orchestration(project, test, &logger) {
                         Ok(orchestration) => {
-                            for test_type in &test.urls {
+                            for test_type in test
+                                .urls
+                                .iter()
+                                .map(|(key, endpoint)| (key, &endpoint.path))
+                            {
                                 self.trip();
                                 match self.run_verification(
                                     &project,
@@ -817,7 +829,7 @@ impl<'a> Benchmarker<'a> {
             let mut endpoint = String::new();
             if let Some(key) = test.urls.keys().next() {
                 if let Some(_endpoint) = test.urls.get(key) {
-                    endpoint = _endpoint.clone();
+                    endpoint = _endpoint.path.clone();
                 }
             }
 
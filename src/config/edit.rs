@@ -0,0 +1,106 @@
+//! The edit module supports making targeted changes to a `config.toml` file
+//! while preserving its comments and formatting.
+
+use crate::error::ToolsetError::ConfigTableNotFoundError;
+use crate::error::ToolsetResult;
+use crate::io;
+use std::path::Path;
+use toml_edit::{Document, Item, Value};
+
+/// Loads the given `config.toml` file as an editable `Document`.
+pub fn load(file: &Path) -> ToolsetResult<Document> {
+    let contents = std::fs::read_to_string(file)?;
+
+    Ok(contents.parse::<Document>()?)
+}
+
+/// Writes the given `Document` back to `file`; see `io::atomic_write`.
+pub fn save(doc: &Document, file: &Path) -> ToolsetResult<()> {
+    io::atomic_write(file, doc.to_string())
+}
+
+/// Sets `key` in the `[framework]` block of `doc` to `value`.
+pub fn set_framework_field<V: Into<Value>>(
+    doc: &mut Document,
+    key: &str,
+    value: V,
+) -> ToolsetResult<()> {
+    set_field(doc, "framework", key, value.into())
+}
+
+/// Sets `key` in the test block named `test` (e.g. `main`) of `doc` to
+/// `value`.
+pub fn set_test_field<V: Into<Value>>(
+    doc: &mut Document,
+    test: &str,
+    key: &str,
+    value: V,
+) -> ToolsetResult<()> {
+    set_field(doc, test, key, value.into())
+}
+
+//
+// PRIVATES
+//
+
+/// Sets `key` in the table `table_name` to `value`, carrying over the
+/// whitespace and trailing comment of the value being replaced.
+fn set_field(doc: &mut Document, table_name: &str, key: &str, value: Value) -> ToolsetResult<()> {
+    let table = match doc.get_mut(table_name).and_then(Item::as_table_like_mut) {
+        Some(table) => table,
+        None => return Err(ConfigTableNotFoundError(table_name.to_string())),
+    };
+
+    let mut value = value;
+    if let Some(existing) = table.get(key).and_then(Item::as_value) {
+        *value.decor_mut() = existing.decor().clone();
+    }
+    table.insert(key, Item::Value(value));
+
+    Ok(())
+}
+
+//
+// TESTS
+//
+
+#[cfg(test)]
+mod tests {
+    use crate::config::edit;
+    use crate::error::ToolsetError::ConfigTableNotFoundError;
+    use toml_edit::Document;
+
+    const CONFIG: &str = r#"# Maintained by the gemini team.
+[framework]
+name = "Gemini" # display name
+github = "https://github.com/KhulnaSoft/gemini"
+
+# The main test.
+[main]
+urls.json = "/json"
+"#;
+
+    #[test]
+    fn it_preserves_comments_when_setting_a_field() {
+        let mut doc = CONFIG.parse::<Document>().unwrap();
+        edit::set_framework_field(&mut doc, "name", "Gemini2").unwrap();
+        edit::set_test_field(&mut doc, "main", "os", "Linux").unwrap();
+
+        let edited = doc.to_string();
+        assert!(edited.contains("# Maintained by the gemini team."));
+        assert!(edited.contains("name = \"Gemini2\" # display name"));
+        assert!(edited.contains("# The main test."));
+        assert!(edited.contains("os = \"Linux\""));
+        assert!(edited.contains("github = \"https://github.com/KhulnaSoft/gemini\""));
+    }
+
+    #[test]
+    fn it_rejects_setting_a_field_on_a_missing_table() {
+        let mut doc = CONFIG.parse::<Document>().unwrap();
+
+        match edit::set_test_field(&mut doc, "postgres", "os", "Linux") {
+            Err(ConfigTableNotFoundError(table)) => assert_eq!(table, "postgres"),
+            other => panic!("expected ConfigTableNotFoundError, got: {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,113 @@
+//! The lint module contains advisory checks of a `Project`'s configuration.
+//! Unlike the errors raised while parsing, lints never prevent a test from
+//! being run.
+
+use crate::config::{current_schema_version, Config, Named, Project};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// The lint raised for a database test without a `database_os`.
+pub const MISSING_DATABASE_OS: &str = "missing-database-os";
+
+/// The lint raised for a config written for a newer `schema_version` than
+/// this toolset understands.
+pub const NEWER_SCHEMA_VERSION: &str = "newer-schema-version";
+
+/// The lint raised for each deprecated field remapped while loading; see
+/// `config::DEPRECATED_FRAMEWORK_FIELDS`.
+pub const DEPRECATED_FIELD: &str = "deprecated-field";
+
+/// The lint raised for a config file using both `\r\n` and `\n` line
+/// endings, which often indicates a botched merge.
+pub const MIXED_LINE_ENDINGS: &str = "mixed-line-endings";
+
+/// An advisory finding about a `Project`'s configuration.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Lint {
+    /// The name of the offending test, if the lint concerns a single test.
+    pub test: Option<String>,
+    /// A stable, kebab-case identifier of the kind of lint, e.g.
+    /// `MISSING_DATABASE_OS`.
+    pub code: &'static str,
+    pub message: String,
+}
+
+/// A config file's `[lint]` table.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct LintSettings {
+    /// The codes of the lints suppressed for the file, e.g.
+    /// `["deprecated-field"]`.
+    #[serde(default)]
+    pub allow: Vec<String>,
+}
+
+impl LintSettings {
+    /// Returns whether the lint with the given `code` is suppressed.
+    pub fn allows(&self, code: &str) -> bool {
+        self.allow.iter().any(|allowed| allowed == code)
+    }
+}
+
+impl fmt::Display for Lint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.test {
+            Some(test) => write!(f, "[{}] {}: {}", self.code, test, self.message),
+            None => write!(f, "[{}] {}", self.code, self.message),
+        }
+    }
+}
+
+/// Runs every lint against the given `project`. Lints whose code the
+/// `lint.allow` of the project's config lists are left out.
+pub fn lint_project(project: &Project) -> Vec<Lint> {
+    let mut lints = Vec::new();
+    lint_missing_database_os(project, &mut lints);
+    if let Some(settings) = &project.lint_settings {
+        lints.retain(|lint| !settings.allows(lint.code));
+    }
+
+    lints
+}
+
+/// Runs the lints which concern a config file as a whole, rather than the
+/// `Project` built from it, against the given `config`. Lints whose code the
+/// config's `lint.allow` lists are left out.
+pub fn lint_config(config: &Config) -> Vec<Lint> {
+    let mut lints = config.load_lints.clone();
+    if let Some(schema_version) = config.schema_version {
+        if schema_version > current_schema_version() {
+            lints.push(Lint {
+                test: None,
+                code: NEWER_SCHEMA_VERSION,
+                message: format!(
+                    "written for schema version {}, but only versions up to {} are supported",
+                    schema_version,
+                    current_schema_version()
+                ),
+            });
+        }
+    }
+    if let Some(settings) = &config.lint_settings {
+        lints.retain(|lint| !settings.allows(lint.code));
+    }
+
+    lints
+}
+
+//
+// PRIVATES
+//
+
+/// A database test must say which OS its database runs on to be scheduled
+/// onto a database host; there is no project-wide default to fall back on.
+fn lint_missing_database_os(project: &Project, lints: &mut Vec<Lint>) {
+    for test in &project.tests {
+        if test.is_database_test() && test.database_os.is_none() {
+            lints.push(Lint {
+                test: test.try_get_name(),
+                code: MISSING_DATABASE_OS,
+                message: "uses a database but does not specify `database_os`".to_string(),
+            });
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use crate::config::{Named, Project, Test};
+use crate::config::{Project, Test};
 use crate::docker::docker_config::DockerConfig;
 use crate::docker::listener::build_image::BuildImage;
 use crate::docker::listener::simple::Simple;
@@ -15,18 +15,10 @@ pub fn build_image(
     test: &Test,
     logger: &Logger,
 ) -> ToolsetResult<String> {
-    let mut dockerfile;
-    if test.dockerfile.is_some() {
-        dockerfile = test.dockerfile.clone().unwrap();
-    } else {
-        dockerfile = test.get_name();
-        dockerfile.push_str(".dockerfile");
-    }
-
     let image_id = dockurl::image::build_image(
         &test.get_tag(),
-        &PathBuf::from(dockerfile),
-        &project.get_path()?,
+        &PathBuf::from(test.get_dockerfile()),
+        &project.docker_context_path()?,
         &config.server_docker_host,
         config.use_unix_socket,
         BuildImage::new(logger),
@@ -13,6 +13,11 @@ impl BuildContainer {
         }
     }
 }
+impl Default for BuildContainer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 impl Handler for BuildContainer {
     fn write(&mut self, data: &[u8]) -> Result<usize, WriteError> {
         if let Ok(logs) = std::str::from_utf8(&data) {
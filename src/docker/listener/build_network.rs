@@ -13,6 +13,11 @@ impl BuildNetwork {
         }
     }
 }
+impl Default for BuildNetwork {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 impl Handler for BuildNetwork {
     fn write(&mut self, data: &[u8]) -> Result<usize, WriteError> {
         if let Ok(logs) = std::str::from_utf8(&data) {
@@ -11,6 +11,11 @@ impl Simple {
         }
     }
 }
+impl Default for Simple {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 impl Handler for Simple {
     fn write(&mut self, data: &[u8]) -> Result<usize, WriteError> {
         if let Ok(logs) = std::str::from_utf8(&data) {
@@ -1,3 +1,4 @@
+use std::error::Error as StdError;
 use std::io;
 
 use thiserror::Error;
@@ -15,20 +16,102 @@ pub enum ToolsetError {
     #[error("IO error occurred")]
     IoError(#[from] io::Error),
 
+    #[error("Not a file: {0}")]
+    NotAFileError(String),
+
     #[error("Toml deserialize error occurred")]
     TomlDeserializeError(#[from] toml::de::Error),
 
     #[error("Toml serialize error occurred")]
     TomlSerializeError(#[from] toml::ser::Error),
 
+    #[error("Toml edit error occurred")]
+    TomlEditError(#[from] toml_edit::TomlError),
+
+    #[error("Table not found in config.toml: {0}")]
+    ConfigTableNotFoundError(String),
+
     #[error("Invalid config.toml: {0}, {1}")]
     InvalidConfigError(String, toml::de::Error),
 
+    #[error(
+        "Invalid config.toml: {0}, {1}; hint: the file looks like YAML, but configs must be TOML"
+    )]
+    YamlLikeConfigError(String, toml::de::Error),
+
+    #[error("Required field is empty in test {0}: {1}")]
+    EmptyRequiredFieldError(String, String),
+
+    #[error("Invalid test name: {0}; names may only contain a-z, 0-9, '-', '_', and '.'")]
+    InvalidTestNameError(String),
+
+    #[error("Test declares no urls: {0}")]
+    NoUrlsError(String),
+
+    #[error("Invalid url in test {0}: {1}; urls must begin with '/'")]
+    InvalidUrlError(String, String),
+
+    #[error("Unknown url key in test {0}: {1}")]
+    UnknownUrlKeyError(String, String),
+
+    #[error("Unknown default_url in test {0}: {1}; it must be a key of urls")]
+    UnknownDefaultUrlError(String, String),
+
+    #[error("Config file too large: {0} is {1} bytes")]
+    ConfigTooLargeError(String, u64),
+
+    #[error("Unsupported schema version in {0}: {1} is no longer supported")]
+    UnsupportedSchemaVersionError(String, u32),
+
+    #[error("Config file is not valid UTF-8: {0}")]
+    NonUtf8ConfigError(String),
+
+    #[error("Alias of test {0} collides with a test name: {1}")]
+    AliasCollisionError(String, String),
+
+    #[error("Invalid dockerfile path in test {0}: {1}; it must be relative and stay within the build context")]
+    InvalidDockerfilePathError(String, String),
+
+    #[error("Invalid {0} link: {1}; links must be http(s) URLs")]
+    InvalidLinkError(String, String),
+
+    #[error("Conflicting framework in {0}: expected {1}, found {2}")]
+    ConflictingFrameworkError(String, String, String),
+
+    #[error(
+        "Missing main test in config file: {0}; hint: every framework must define a `[main]` test"
+    )]
+    MissingMainTestError(String),
+
+    #[error("Unknown HTTP method for an endpoint of test {0}: {1}")]
+    UnknownHttpMethodError(String, String),
+
+    #[error("Invalid memory for resources of test {0}: {1}; expected a number with a k, m, or g suffix, e.g. \"2g\"")]
+    InvalidMemoryError(String, String),
+
+    #[error("Test {0} sets both `resources` and `min_cpus`/`min_mem_mb`; set only `resources`")]
+    ConflictingResourcesError(String),
+
+    #[error("Port {0} is declared by more than one test: {1}")]
+    DuplicatePortError(u16, String),
+
+    #[error("Invalid base URL: {0}")]
+    InvalidBaseUrlError(String),
+
     #[error("Serde json error")]
     SerdeJsonError(#[from] serde_json::error::Error),
 
-    #[error("Language not found for config file: {0}; {1}")]
-    LanguageNotFoundError(String, String),
+    #[error("Cyclic include in config file: {0}")]
+    CyclicIncludeError(String),
+
+    #[error(
+        "Language not found for config file: {0}; {1}{}",
+        did_you_mean(.2)
+    )]
+    LanguageNotFoundError(String, String, Vec<String>),
+
+    #[error("Failed to list the files changed since {0}: {1}")]
+    GitDiffError(String, String),
 
     #[error("CtrlC Error occurred")]
     CtrlCError(#[from] ctrlc::Error),
@@ -36,6 +119,24 @@ pub enum ToolsetError {
     #[error("Invalid FrameworkBenchmarks directory: {0}")]
     InvalidFrameworkBenchmarksDirError(String),
 
+    #[error("Frameworks directory does not exist: {0}")]
+    FrameworksDirMissingError(String),
+
+    #[error("Project directory {0} is not beneath the frameworks directory {1}")]
+    ProjectOutsideFrameworksDirError(String, String),
+
+    #[error("Refusing to scaffold into a non-empty directory: {0}")]
+    ScaffoldDirNotEmptyError(String),
+
+    #[error("Docker build context directory does not exist: {0}")]
+    DockerContextNotFoundError(String),
+
+    #[error("Dockerfile not found for test {0}: {1}")]
+    DockerfileNotFoundError(String, String),
+
+    #[error("Dockerfile for test {0} is not a file: {1}")]
+    DockerfileNotAFileError(String, String),
+
     #[error("Docker Container did not respond")]
     NoResponseFromDockerContainerError,
 
@@ -63,3 +164,341 @@ pub enum ToolsetError {
     #[error("Failed to parse benchmark results")]
     BenchmarkDataParseError,
 }
+
+/// The process exit codes reported for each category of `ToolsetError`.
+pub mod exit_codes {
+    /// Errors not covered by a more specific category.
+    pub const GENERAL: i32 = 1;
+    /// The command line arguments were invalid.
+    pub const USAGE: i32 = 2;
+    /// A config file was malformed or failed validation.
+    pub const CONFIG: i32 = 3;
+    /// Reading or writing a file failed.
+    pub const IO: i32 = 4;
+    /// A required directory, language, or config block could not be found.
+    pub const NOT_FOUND: i32 = 5;
+    /// Communicating with Docker, or a container it runs, failed.
+    pub const DOCKER: i32 = 6;
+    /// A test implementation failed verification or benchmarking.
+    pub const TEST_FAILURE: i32 = 7;
+}
+
+impl ToolsetError {
+    /// Returns the process exit code for this error's category; see
+    /// `exit_codes` for the mapping. Codes are stable across releases.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            ToolsetError::UnknownBenchmarkerModeError(_) => exit_codes::USAGE,
+
+            ToolsetError::TomlDeserializeError(_)
+            | ToolsetError::TomlSerializeError(_)
+            | ToolsetError::TomlEditError(_)
+            | ToolsetError::InvalidConfigError(_, _)
+            | ToolsetError::YamlLikeConfigError(_, _)
+            | ToolsetError::EmptyRequiredFieldError(_, _)
+            | ToolsetError::InvalidTestNameError(_)
+            | ToolsetError::NoUrlsError(_)
+            | ToolsetError::InvalidUrlError(_, _)
+            | ToolsetError::UnknownUrlKeyError(_, _)
+            | ToolsetError::UnknownDefaultUrlError(_, _)
+            | ToolsetError::ConfigTooLargeError(_, _)
+            | ToolsetError::UnsupportedSchemaVersionError(_, _)
+            | ToolsetError::NonUtf8ConfigError(_)
+            | ToolsetError::AliasCollisionError(_, _)
+            | ToolsetError::InvalidDockerfilePathError(_, _)
+            | ToolsetError::InvalidLinkError(_, _)
+            | ToolsetError::ConflictingFrameworkError(_, _, _)
+            | ToolsetError::DuplicatePortError(_, _)
+            | ToolsetError::MissingMainTestError(_)
+            | ToolsetError::UnknownHttpMethodError(_, _)
+            | ToolsetError::InvalidMemoryError(_, _)
+            | ToolsetError::ConflictingResourcesError(_)
+            | ToolsetError::InvalidBaseUrlError(_)
+            | ToolsetError::CyclicIncludeError(_) => exit_codes::CONFIG,
+
+            ToolsetError::IoError(_)
+            | ToolsetError::NotAFileError(_)
+            | ToolsetError::ScaffoldDirNotEmptyError(_) => exit_codes::IO,
+
+            ToolsetError::ConfigTableNotFoundError(_)
+            | ToolsetError::LanguageNotFoundError(_, _, _)
+            | ToolsetError::InvalidFrameworkBenchmarksDirError(_)
+            | ToolsetError::FrameworksDirMissingError(_)
+            | ToolsetError::ProjectOutsideFrameworksDirError(_, _)
+            | ToolsetError::DockerContextNotFoundError(_)
+            | ToolsetError::DockerfileNotAFileError(_, _)
+            | ToolsetError::DockerfileNotFoundError(_, _) => exit_codes::NOT_FOUND,
+
+            ToolsetError::DockerError(_)
+            | ToolsetError::NoResponseFromDockerContainerError
+            | ToolsetError::AppServerContainerShutDownError
+            | ToolsetError::ContainerPortMappingInspectionError
+            | ToolsetError::ExposePortError => exit_codes::DOCKER,
+
+            ToolsetError::DebugFailedException
+            | ToolsetError::VerificationFailedException
+            | ToolsetError::FailedBenchmarkCommandRetrievalError
+            | ToolsetError::BenchmarkDataParseError => exit_codes::TEST_FAILURE,
+
+            ToolsetError::CurlError(_)
+            | ToolsetError::SerdeJsonError(_)
+            | ToolsetError::CtrlCError(_)
+            | ToolsetError::GitDiffError(_, _) => exit_codes::GENERAL,
+        }
+    }
+
+    /// Returns whether this error reports that something required, such as a
+    /// language, directory, or config block, could not be found.
+    pub fn is_not_found(&self) -> bool {
+        self.exit_code() == exit_codes::NOT_FOUND
+    }
+
+    /// Returns whether this error reports a malformed or invalid config.
+    pub fn is_config_error(&self) -> bool {
+        self.exit_code() == exit_codes::CONFIG
+    }
+
+    /// Serializes this error for machine consumers such as CI systems, as
+    /// `{"kind": ..., "path": ..., "message": ..., "causes": [...]}`: `kind`
+    /// is the variant's name, `path` the file or directory it concerns (or
+    /// `null`), `message` its `Display`, and `causes` the messages of the
+    /// errors it wraps, outermost first.
+    pub fn to_json(&self) -> String {
+        let mut causes = Vec::new();
+        let mut source = self.source();
+        while let Some(error) = source {
+            causes.push(error.to_string());
+            source = error.source();
+        }
+
+        serde_json::json!({
+            "kind": self.kind(),
+            "path": self.path(),
+            "message": self.to_string(),
+            "causes": causes,
+        })
+        .to_string()
+    }
+
+    /// Formats this error for the terminal. With `color`, the label (the
+    /// message up to its first `:`) is red and the path it concerns, if any,
+    /// is highlighted; without, the message is exactly this error's
+    /// `Display`. The escape codes are written directly, rather than through
+    /// `colored`, so that `color` alone decides whether they appear.
+    ///
+    /// A config which failed to parse is followed by the offending line with
+    /// a caret beneath the failing column.
+    pub fn render_pretty(&self, color: bool) -> String {
+        let message = self.to_string();
+        let mut rendered = if color {
+            let (label, rest) = match message.split_once(':') {
+                Some((label, rest)) => (label, rest),
+                None => (message.as_str(), ""),
+            };
+            let rest = match self.path() {
+                Some(path) => rest.replace(path, &format!("{}{}{}", CYAN, path, RESET)),
+                None => rest.to_string(),
+            };
+            let separator = if rest.is_empty() { "" } else { ":" };
+            format!("{}{}{}{}{}", RED, label, RESET, separator, rest)
+        } else {
+            message
+        };
+
+        if let ToolsetError::InvalidConfigError(path, error)
+        | ToolsetError::YamlLikeConfigError(path, error) = self
+        {
+            if let Some((line, column)) = error.line_col() {
+                if let Some(source) = std::fs::read_to_string(path)
+                    .ok()
+                    .and_then(|contents| contents.lines().nth(line).map(String::from))
+                {
+                    let number = (line + 1).to_string();
+                    let caret = if color {
+                        format!("{}^{}", RED, RESET)
+                    } else {
+                        "^".to_string()
+                    };
+                    rendered.push_str(&format!(
+                        "\n{} | {}\n{} | {}{}",
+                        number,
+                        source,
+                        " ".repeat(number.len()),
+                        " ".repeat(column),
+                        caret
+                    ));
+                }
+            }
+        }
+
+        rendered
+    }
+
+    /// Returns the name of this error's variant, e.g. `InvalidConfigError`.
+    fn kind(&self) -> String {
+        // Every variant's `Debug` begins with its name.
+        format!("{:?}", self)
+            .chars()
+            .take_while(char::is_ascii_alphanumeric)
+            .collect()
+    }
+
+    /// Returns the path of the file or directory this error concerns, if
+    /// any.
+    fn path(&self) -> Option<&str> {
+        match self {
+            ToolsetError::NotAFileError(path)
+            | ToolsetError::InvalidConfigError(path, _)
+            | ToolsetError::YamlLikeConfigError(path, _)
+            | ToolsetError::ConfigTooLargeError(path, _)
+            | ToolsetError::UnsupportedSchemaVersionError(path, _)
+            | ToolsetError::NonUtf8ConfigError(path)
+            | ToolsetError::MissingMainTestError(path)
+            | ToolsetError::CyclicIncludeError(path)
+            | ToolsetError::LanguageNotFoundError(_, path, _)
+            | ToolsetError::InvalidFrameworkBenchmarksDirError(path)
+            | ToolsetError::FrameworksDirMissingError(path)
+            | ToolsetError::DockerContextNotFoundError(path)
+            | ToolsetError::DockerfileNotAFileError(_, path)
+            | ToolsetError::DockerfileNotFoundError(_, path) => Some(path),
+            _ => None,
+        }
+    }
+}
+
+/// The ANSI escape codes used by `ToolsetError::render_pretty`.
+const RED: &str = "\x1b[31m";
+const CYAN: &str = "\x1b[36m";
+const RESET: &str = "\x1b[0m";
+
+/// Renders the `; did you mean one of: ...` hint for `candidates`, or nothing
+/// when there are none.
+fn did_you_mean(candidates: &[String]) -> String {
+    if candidates.is_empty() {
+        String::new()
+    } else {
+        format!("; did you mean one of: {}", candidates.join(", "))
+    }
+}
+
+//
+// TESTS
+//
+
+#[cfg(test)]
+mod tests {
+    use crate::config;
+    use crate::error::exit_codes;
+    use crate::error::ToolsetError::{
+        CyclicIncludeError, FrameworksDirMissingError, InvalidConfigError, IoError,
+        LanguageNotFoundError, NotAFileError, UnknownBenchmarkerModeError, UnknownUrlKeyError,
+        VerificationFailedException,
+    };
+
+    #[test]
+    fn it_maps_errors_to_exit_codes() {
+        assert_eq!(
+            FrameworksDirMissingError("frameworks".to_string()).exit_code(),
+            exit_codes::NOT_FOUND
+        );
+        assert_eq!(
+            IoError(std::io::Error::from(std::io::ErrorKind::NotFound)).exit_code(),
+            exit_codes::IO
+        );
+        assert_eq!(
+            UnknownBenchmarkerModeError("fast".to_string()).exit_code(),
+            exit_codes::USAGE
+        );
+        assert_eq!(
+            VerificationFailedException.exit_code(),
+            exit_codes::TEST_FAILURE
+        );
+    }
+
+    #[test]
+    fn it_can_classify_errors() {
+        let not_found =
+            LanguageNotFoundError("gemini".to_string(), "config.toml".to_string(), Vec::new());
+        assert!(not_found.is_not_found());
+        assert!(!not_found.is_config_error());
+
+        let config_error = UnknownUrlKeyError("gemini".to_string(), "graphql".to_string());
+        assert!(config_error.is_config_error());
+        assert!(!config_error.is_not_found());
+        assert!(CyclicIncludeError("config.toml".to_string()).is_config_error());
+
+        let io_error = IoError(std::io::Error::from(std::io::ErrorKind::NotFound));
+        assert!(!io_error.is_not_found());
+        assert!(!io_error.is_config_error());
+    }
+
+    #[test]
+    fn it_only_suggests_languages_when_there_are_candidates() {
+        let without =
+            LanguageNotFoundError("gemini".to_string(), "config.toml".to_string(), Vec::new());
+        assert_eq!(
+            without.to_string(),
+            "Language not found for config file: gemini; config.toml"
+        );
+
+        let with = LanguageNotFoundError(
+            "gemini".to_string(),
+            "config.toml".to_string(),
+            vec!["Java".to_string()],
+        );
+        assert!(with.to_string().ends_with("; did you mean one of: Java"));
+    }
+
+    #[test]
+    fn it_can_render_errors_for_the_terminal() {
+        let error = NotAFileError("/bw/frameworks/Java/gemini/config.toml".to_string());
+        assert_eq!(error.render_pretty(false), error.to_string());
+
+        let colored = error.render_pretty(true);
+        assert!(colored.starts_with("\x1b[31mNot a file\x1b[0m:"));
+        assert!(colored.contains("\x1b[36m/bw/frameworks/Java/gemini/config.toml\x1b[0m"));
+    }
+
+    #[test]
+    fn it_serializes_errors_to_json() {
+        let error = InvalidConfigError(
+            "/bw/frameworks/Java/gemini/config.toml".to_string(),
+            "name = = \"Gemini\"".parse::<toml::Value>().unwrap_err(),
+        );
+
+        let json: serde_json::Value = serde_json::from_str(&error.to_json()).unwrap();
+        assert_eq!(json["kind"], "InvalidConfigError");
+        assert_eq!(json["path"], "/bw/frameworks/Java/gemini/config.toml");
+        assert_eq!(json["message"], error.to_string());
+
+        let json: serde_json::Value =
+            serde_json::from_str(&IoError(std::io::Error::other("disk on fire")).to_json())
+                .unwrap();
+        assert_eq!(json["kind"], "IoError");
+        assert!(json["path"].is_null());
+        assert_eq!(json["causes"], serde_json::json!(["disk on fire"]));
+    }
+
+    #[test]
+    fn it_points_at_the_failing_column_of_a_config() {
+        let mut dir = std::env::temp_dir();
+        dir.push(format!(
+            "bw_plugins_render_pretty_{}",
+            uuid::Uuid::from_u128(rand::random())
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        let config = dir.join("config.toml");
+        std::fs::write(&config, "[framework]\nname = = \"Gemini\"\n").unwrap();
+
+        match config::load(&config) {
+            Err(error @ InvalidConfigError(..)) => {
+                let rendered = error.render_pretty(false);
+                assert!(rendered.starts_with(&error.to_string()));
+                assert!(rendered.ends_with("\n2 | name = = \"Gemini\"\n  |        ^"));
+            }
+            other => panic!("expected InvalidConfigError, got: {:?}", other),
+        }
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
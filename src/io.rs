@@ -1,6 +1,6 @@
-use crate::config::{Named, Test};
+use crate::config::{Config, Named, Test};
 use crate::docker::Verification;
-use crate::error::ToolsetError::InvalidFrameworkBenchmarksDirError;
+use crate::error::ToolsetError::{FrameworksDirMissingError, InvalidFrameworkBenchmarksDirError};
 use crate::error::{ToolsetError, ToolsetResult};
 use crate::metadata;
 use crate::results::Results;
@@ -10,7 +10,9 @@ use std::collections::HashMap;
 use std::env;
 use std::fs::{File, OpenOptions};
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use uuid::Uuid;
 
 /// `Logger` is used for logging to stdout and optionally to a file.
 ///
@@ -26,12 +28,12 @@ pub struct Logger {
     pub quiet: bool,
 }
 
-impl Logger {
+impl Default for Logger {
     /// Helper function for creating a simple Logger which will only print to
     /// stdout by default.
     /// Note: this Logger can later be configured to write to a file, but the
     /// other convenience functions are probably preferable.
-    pub fn default() -> Logger {
+    fn default() -> Logger {
         Logger {
             prefix: None,
             results_dir: None,
@@ -40,7 +42,9 @@ impl Logger {
             quiet: false,
         }
     }
+}
 
+impl Logger {
     /// Helper function for creating a simple Logger with a given `prefix`.
     /// Note: this Logger can later be configured to write to a file, but the
     /// other convenience functions are probably preferable.
@@ -201,30 +205,107 @@ pub fn print_all_tests_for_framework(framework: &str) -> ToolsetResult<()> {
     print_all(metadata::list_tests_for_framework(framework))
 }
 
+#[cfg(test)]
+thread_local! {
+    static BW_DIR_OVERRIDE: std::cell::RefCell<Option<PathBuf>> =
+        const { std::cell::RefCell::new(None) };
+}
+
+/// Overrides the `FrameworkBenchmarks` directory returned by `get_bw_dir` for
+/// the current thread, bypassing the cache, so that tests can each point at
+/// their own checkout; passing `None` restores the default resolution.
+#[cfg(test)]
+pub fn set_bw_dir(bw_dir: Option<PathBuf>) {
+    BW_DIR_OVERRIDE.with(|bw_dir_override| *bw_dir_override.borrow_mut() = bw_dir);
+}
+
+/// The resolution of `get_bw_dir`, shared by all threads until
+/// `refresh_bw_dir` is called.
+static BW_DIR_CACHE: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+/// The file which, like a `frameworks` directory, marks the root of a
+/// `FrameworkBenchmarks` checkout.
+pub const BW_ROOT_MARKER: &str = ".bwroot";
+
 /// Gets the `FrameworkBenchmarks` `PathBuf` for the running context.
+///
+/// Resolution order is: the nearest ancestor of the current directory
+/// marked as a `FrameworkBenchmarks` root (see `find_bw_root_from`),
+/// `BW_HOME`, `~/.bw`, and finally the current directory. The directory is
+/// resolved once for the process and cached; see `refresh_bw_dir`.
 pub fn get_bw_dir() -> ToolsetResult<PathBuf> {
-    let mut bw_path = PathBuf::new();
-    if let Ok(bw_home) = env::var("BW_HOME") {
-        bw_path.push(bw_home);
-    } else if let Some(mut home_dir) = dirs::home_dir() {
-        home_dir.push(".bw");
-        bw_path = home_dir;
-        if !bw_path.exists() {
-            if let Ok(current_dir) = env::current_dir() {
-                bw_path = current_dir;
-            }
-        }
+    #[cfg(test)]
+    if let Some(bw_dir) = BW_DIR_OVERRIDE.with(|bw_dir_override| bw_dir_override.borrow().clone()) {
+        return validate_bw_dir(bw_dir);
     }
 
-    let mut frameworks_dir = bw_path.clone();
+    cached_bw_dir(&BW_DIR_CACHE, || validate_bw_dir(resolve_bw_dir()))
+}
+
+/// Clears the cached `get_bw_dir` resolution for every thread so that the
+/// next call resolves it anew, e.g. after `BW_HOME` or the current directory
+/// changes.
+pub fn refresh_bw_dir() {
+    clear_bw_dir(&BW_DIR_CACHE);
+}
+
+/// Walks up from `start`, like git does to find its root, and returns the
+/// first directory containing either a `BW_ROOT_MARKER` file or a
+/// `frameworks` directory.
+pub fn find_bw_root_from(start: &Path) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .find(|dir| dir.join(BW_ROOT_MARKER).is_file() || dir.join("frameworks").is_dir())
+        .map(Path::to_path_buf)
+}
+
+/// Gets the `frameworks` sub-directory of the `FrameworkBenchmarks` directory
+/// under which all test implementations live.
+pub fn frameworks_dir() -> ToolsetResult<PathBuf> {
+    let mut frameworks_dir = get_bw_dir()?;
     frameworks_dir.push("frameworks");
-    if !frameworks_dir.exists() {
-        return Err(InvalidFrameworkBenchmarksDirError(
+    if !frameworks_dir.is_dir() {
+        return Err(FrameworksDirMissingError(
             frameworks_dir.to_str().unwrap().to_string(),
         ));
     }
 
-    Ok(bw_path)
+    Ok(frameworks_dir)
+}
+
+/// Writes `contents` to the file at `path` by first writing a temporary file
+/// in the same directory and then renaming it over `path`, so that `path` is
+/// never left partially written.
+pub fn atomic_write<C: AsRef<[u8]>>(path: &Path, contents: C) -> ToolsetResult<()> {
+    let mut temp_path = path.to_path_buf();
+    temp_path.set_file_name(format!(
+        ".{}.{}.tmp",
+        path.file_name().unwrap().to_str().unwrap(),
+        Uuid::from_u128(rand::random::<u128>()).to_simple()
+    ));
+
+    let result = File::create(&temp_path)
+        .and_then(|mut file| {
+            file.write_all(contents.as_ref())?;
+            file.sync_all()
+        })
+        .and_then(|_| std::fs::rename(&temp_path, path));
+    if let Err(e) = result {
+        let _ = std::fs::remove_file(&temp_path);
+        return Err(e.into());
+    }
+
+    Ok(())
+}
+
+/// Serializes `config` and atomically writes it to `path`, creating any
+/// missing parent directories.
+pub fn write_config(path: &Path, config: &Config) -> ToolsetResult<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    atomic_write(path, config.to_toml()?)
 }
 
 /// Creates the result directory and timestamp subdirectory for this run.
@@ -313,17 +394,145 @@ fn print_all<T: Named>(result: Result<Vec<T>, ToolsetError>) -> ToolsetResult<()
     }
 }
 
+/// Returns the path held by `cache`, first filling it from `resolve` if it is
+/// empty. A failed resolution is not cached.
+fn cached_bw_dir<F>(cache: &Mutex<Option<PathBuf>>, resolve: F) -> ToolsetResult<PathBuf>
+where
+    F: FnOnce() -> ToolsetResult<PathBuf>,
+{
+    let mut cached = cache.lock().unwrap();
+    if let Some(bw_dir) = &*cached {
+        return Ok(bw_dir.clone());
+    }
+    let bw_dir = resolve()?;
+    *cached = Some(bw_dir.clone());
+
+    Ok(bw_dir)
+}
+
+/// Empties `cache`, so that the next `cached_bw_dir` resolves it anew.
+fn clear_bw_dir(cache: &Mutex<Option<PathBuf>>) {
+    *cache.lock().unwrap() = None;
+}
+
+/// Resolves the `FrameworkBenchmarks` directory, ignoring the `set_bw_dir`
+/// override; see `get_bw_dir`.
+fn resolve_bw_dir() -> PathBuf {
+    let mut bw_path = PathBuf::new();
+    if let Some(bw_root) = env::current_dir()
+        .ok()
+        .and_then(|current_dir| find_bw_root_from(&current_dir))
+    {
+        bw_path = bw_root;
+    } else if let Ok(bw_home) = env::var("BW_HOME") {
+        bw_path.push(bw_home);
+    } else if let Some(mut home_dir) = dirs::home_dir() {
+        home_dir.push(".bw");
+        bw_path = home_dir;
+        if !bw_path.exists() {
+            if let Ok(current_dir) = env::current_dir() {
+                bw_path = current_dir;
+            }
+        }
+    }
+
+    bw_path
+}
+
+/// Returns `bw_path` if it is a directory.
+fn validate_bw_dir(bw_path: PathBuf) -> ToolsetResult<PathBuf> {
+    if !bw_path.is_dir() {
+        return Err(InvalidFrameworkBenchmarksDirError(
+            bw_path.to_str().unwrap().to_string(),
+        ));
+    }
+
+    Ok(bw_path)
+}
+
+/// Helpers for tests which need a `FrameworkBenchmarks` root on disk rather
+/// than the fixtures under `BW_HOME`.
+#[cfg(test)]
+pub mod testing {
+    use std::path::PathBuf;
+
+    /// Removes the temporary `FrameworkBenchmarks` root it guards, and clears
+    /// any `set_bw_dir` override, when dropped.
+    pub struct TempBwDir {
+        path: PathBuf,
+    }
+
+    impl Drop for TempBwDir {
+        fn drop(&mut self) {
+            super::set_bw_dir(None);
+            let _ = std::fs::remove_dir_all(&self.path);
+        }
+    }
+
+    /// Materializes a temporary `FrameworkBenchmarks` root containing
+    /// `frameworks/{language}/{framework}/config.toml` with a minimal `main`
+    /// test and its `{framework}.dockerfile`, and returns the root along with
+    /// a guard that cleans it up.
+    pub fn temp_project_dir(language: &str, framework: &str) -> (PathBuf, TempBwDir) {
+        let mut root = std::env::temp_dir();
+        root.push(format!(
+            "bw_plugins_{}_{}",
+            framework.to_lowercase(),
+            uuid::Uuid::from_u128(rand::random())
+        ));
+        let project_dir = root.join("frameworks").join(language).join(framework);
+        std::fs::create_dir_all(&project_dir).unwrap();
+        std::fs::write(
+            project_dir.join("config.toml"),
+            format!(
+                r#"[framework]
+name = "{}"
+
+[main]
+urls.json = "/json"
+approach = "Realistic"
+classification = "Fullstack"
+platform = "None"
+webserver = "None"
+os = "Linux"
+versus = ""
+"#,
+                framework
+            ),
+        )
+        .unwrap();
+        std::fs::write(
+            project_dir.join(format!("{}.dockerfile", framework.to_lowercase())),
+            "FROM scratch\n",
+        )
+        .unwrap();
+
+        (root.clone(), TempBwDir { path: root })
+    }
+}
+
 //
 // TESTS
 //
 
 #[cfg(test)]
 mod tests {
+    use crate::config;
+    use crate::config::{Endpoint, Named};
+    use crate::io::frameworks_dir;
     use crate::io::get_bw_dir;
     use crate::io::print_all_frameworks;
     use crate::io::print_all_tests;
     use crate::io::print_all_tests_with_tag;
+    use crate::io::testing::temp_project_dir;
+    use crate::io::{
+        atomic_write, cached_bw_dir, clear_bw_dir, find_bw_root_from, refresh_bw_dir, set_bw_dir,
+        write_config, BW_ROOT_MARKER,
+    };
     use crate::metadata::TAG_BROKEN;
+    use std::path::PathBuf;
+    use std::sync::{Arc, Mutex};
+    use std::thread;
 
     #[test]
     fn it_will_get_a_valid_bw_dir() {
@@ -333,6 +542,50 @@ mod tests {
         };
     }
 
+    #[test]
+    fn it_caches_the_bw_dir_until_refreshed() {
+        let cache = Arc::new(Mutex::new(None));
+        let first = PathBuf::from("/first");
+        let second = PathBuf::from("/second");
+
+        assert_eq!(cached_bw_dir(&cache, || Ok(first.clone())).unwrap(), first);
+        let reader = Arc::clone(&cache);
+        let stale = thread::spawn(move || cached_bw_dir(&reader, || Ok("/second".into())))
+            .join()
+            .unwrap();
+        assert_eq!(stale.unwrap(), first);
+
+        let refresher = Arc::clone(&cache);
+        thread::spawn(move || clear_bw_dir(&refresher))
+            .join()
+            .unwrap();
+        assert_eq!(
+            cached_bw_dir(&cache, || Ok(second.clone())).unwrap(),
+            second
+        );
+    }
+
+    #[test]
+    fn it_resolves_the_bw_dir_anew_after_a_refresh() {
+        let resolved = get_bw_dir().unwrap();
+        let (bw_dir, _guard) = temp_project_dir("Java", "gemini");
+        set_bw_dir(Some(bw_dir.clone()));
+        assert_eq!(get_bw_dir().unwrap(), bw_dir);
+        set_bw_dir(None);
+
+        refresh_bw_dir();
+        assert_eq!(get_bw_dir().unwrap(), resolved);
+        assert!(resolved.join("frameworks").is_dir());
+    }
+
+    #[test]
+    fn it_will_get_a_valid_frameworks_dir() {
+        match frameworks_dir() {
+            Ok(_) => {}
+            Err(e) => panic!("io::frameworks_dir failed. error: {:?}", e),
+        };
+    }
+
     #[test]
     fn it_can_print_all_tests() {
         match print_all_tests() {
@@ -356,4 +609,115 @@ mod tests {
             Err(e) => panic!("io::print_all_tests_with_tag failed. error: {:?}", e),
         };
     }
+
+    #[test]
+    fn it_can_atomically_write_a_file() {
+        let mut dir = std::env::temp_dir();
+        dir.push(format!(
+            "bw_plugins_atomic_write_{}",
+            uuid::Uuid::from_u128(rand::random())
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("config.toml");
+        std::fs::write(&path, "old").unwrap();
+
+        atomic_write(&path, "[framework]\nname = \"Gemini\"\n").unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "[framework]\nname = \"Gemini\"\n"
+        );
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn it_can_materialize_a_temp_project_dir() {
+        let (root, guard) = temp_project_dir("Java", "gemini");
+        let config = root.join("frameworks/Java/gemini/config.toml");
+        match config::get_test_implementations_by_config_file(&config) {
+            Ok(tests) => {
+                assert_eq!(tests.len(), 1);
+                assert_eq!(tests[0].get_name(), "gemini");
+            }
+            Err(e) => panic!("failed to parse the temp config. error: {:?}", e),
+        }
+
+        drop(guard);
+        assert!(!root.exists());
+    }
+
+    #[test]
+    fn it_can_write_a_config_which_reparses_to_the_same_config() {
+        let (root, _guard) = temp_project_dir("Java", "gemini");
+        let original = root.join("frameworks/Java/gemini/config.toml");
+        let mut written = config::load(&original).unwrap();
+        written.framework.authors = Some(vec!["Alice <alice@example.com>".to_string()]);
+        written.main.environment = Some(
+            [("MODE".to_string(), "prod".to_string())]
+                .iter()
+                .cloned()
+                .collect(),
+        );
+
+        let path = root.join("frameworks/Java/gemini2/config.toml");
+        write_config(&path, &written).unwrap();
+
+        let mut reloaded = config::load(&path).unwrap();
+        assert_eq!(reloaded.source_path, Some(path));
+        reloaded.source_path = written.source_path.clone();
+        assert_eq!(reloaded, written);
+    }
+
+    #[test]
+    fn it_keeps_inline_endpoints_when_writing_a_config() {
+        let (root, _guard) = temp_project_dir("Java", "gemini");
+        let original = root.join("frameworks/Java/gemini/config.toml");
+        let mut written = config::load(&original).unwrap();
+        written.main.urls.insert(
+            "db".to_string(),
+            Endpoint {
+                path: "/db".to_string(),
+                status: Some(201),
+                method: None,
+            },
+        );
+        written.main.urls.insert(
+            "update".to_string(),
+            Endpoint {
+                path: "/update".to_string(),
+                status: None,
+                method: Some("POST".to_string()),
+            },
+        );
+
+        let path = root.join("frameworks/Java/gemini2/config.toml");
+        write_config(&path, &written).unwrap();
+
+        let reloaded = config::load(&path).unwrap();
+        assert_eq!(reloaded.main.urls, written.main.urls);
+        assert_eq!(reloaded.main.endpoint("db").unwrap().status, Some(201));
+        assert_eq!(reloaded.main.endpoint("json").unwrap().status, None);
+        assert_eq!(reloaded.main.method_for("update"), "POST");
+        assert_eq!(reloaded.main.method_for("db"), "GET");
+    }
+
+    #[test]
+    fn it_can_find_the_bw_root_from_a_nested_dir() {
+        let mut bw_dir = std::env::temp_dir();
+        bw_dir.push(format!(
+            "bw_plugins_bw_root_{}",
+            uuid::Uuid::from_u128(rand::random())
+        ));
+        let nested = bw_dir.join("frameworks/Java/gemini/src");
+        std::fs::create_dir_all(&nested).unwrap();
+        assert_eq!(find_bw_root_from(&nested), Some(bw_dir.clone()));
+
+        let marked = bw_dir.join("frameworks/Java/gemini");
+        std::fs::write(marked.join(BW_ROOT_MARKER), "").unwrap();
+        assert_eq!(find_bw_root_from(&nested), Some(marked));
+
+        std::fs::remove_dir_all(bw_dir).unwrap();
+    }
 }
@@ -0,0 +1,13 @@
+pub mod benchmarker;
+pub mod cli;
+pub mod config;
+pub mod docker;
+pub mod error;
+pub mod io;
+pub mod metadata;
+pub mod options;
+pub mod results;
+
+#[macro_use]
+extern crate lazy_static;
+extern crate regex;
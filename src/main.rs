@@ -1,18 +1,5 @@
-mod benchmarker;
-mod cli;
-mod config;
-mod docker;
-mod error;
-mod io;
-mod metadata;
-mod options;
-mod results;
-
-#[macro_use]
-extern crate lazy_static;
-extern crate regex;
-
-use crate::error::ToolsetResult;
+use bw_plugins::cli;
+use bw_plugins::error::ToolsetResult;
 
 fn main() -> ToolsetResult<()> {
     cli::run()
@@ -3,7 +3,7 @@
 //! structs representing those configuration files.
 
 use crate::config::{Framework, Named, Project, Test};
-use crate::error::ToolsetResult;
+use crate::error::{ToolsetError, ToolsetResult};
 use crate::io::Logger;
 use crate::{config, io, options};
 use clap::ArgMatches;
@@ -17,8 +17,8 @@ pub const TAG_BROKEN: &str = "broken";
 /// level `framework` to the return Vec.
 pub fn list_all_frameworks() -> ToolsetResult<Vec<Framework>> {
     let mut frameworks: Vec<Framework> = Vec::new();
-    let mut bw_path = io::get_bw_dir()?;
-    bw_path.push("frameworks/*/*/config.toml");
+    let mut bw_path = io::frameworks_dir()?;
+    bw_path.push("*/*/config.toml");
     for path in glob(bw_path.to_str().unwrap()).unwrap() {
         frameworks.push(config::get_framework_by_config_file(&path.unwrap())?);
     }
@@ -30,8 +30,8 @@ pub fn list_all_frameworks() -> ToolsetResult<Vec<Framework>> {
 /// test implementations' `config.toml`, parse each file, and pushes the top-
 /// level `tests` to the return Vec.
 pub fn list_all_tests() -> ToolsetResult<Vec<Test>> {
-    let mut bw_path = io::get_bw_dir()?;
-    bw_path.push("frameworks/*/*/config.toml");
+    let mut bw_path = io::frameworks_dir()?;
+    bw_path.push("*/*/config.toml");
 
     get_test_implementations_by_path(&bw_path)
 }
@@ -40,11 +40,8 @@ pub fn list_all_tests() -> ToolsetResult<Vec<Test>> {
 /// test implementations' `config.toml`, parse each file, and pushes each test
 /// implementation found.
 pub fn list_tests_for_framework(framework_name: &str) -> ToolsetResult<Vec<Test>> {
-    let mut bw_path = io::get_bw_dir()?;
-    bw_path.push(format!(
-        "frameworks/*/{}/config.toml",
-        framework_name.to_lowercase()
-    ));
+    let mut bw_path = io::frameworks_dir()?;
+    bw_path.push(format!("*/{}/config.toml", framework_name.to_lowercase()));
 
     get_test_implementations_by_path(&bw_path)
 }
@@ -54,8 +51,8 @@ pub fn list_tests_for_framework(framework_name: &str) -> ToolsetResult<Vec<Test>
 /// level `Test`s with the given `tag` to the return Vec.
 pub fn list_tests_by_tag(tag: &str) -> ToolsetResult<Vec<Test>> {
     let mut test_implementations = Vec::new();
-    let mut bw_path = io::get_bw_dir()?;
-    bw_path.push("frameworks/*/*/config.toml");
+    let mut bw_path = io::frameworks_dir()?;
+    bw_path.push("*/*/config.toml");
     for path in glob(bw_path.to_str().unwrap()).unwrap() {
         for test in config::get_test_implementations_by_config_file(&path.unwrap())? {
             if test.tags.is_some() && test.clone().tags.unwrap().contains(&tag.to_string()) {
@@ -79,20 +76,23 @@ pub fn list_tests_by_tag(tag: &str) -> ToolsetResult<Vec<Test>> {
 /// Say that (Java, FooFramework) and (C#, FooFramework)
 /// both have a `default` test implementation, then this would return the
 /// `Project`s for both when queried with "FooFramework".
+///
+/// When `exclude_broken` is set, `Test`s tagged as `broken` are skipped.
 pub fn list_projects_by_test_name(
     test_name: Option<String>,
     test_type: Option<&str>,
+    exclude_broken: bool,
 ) -> ToolsetResult<Vec<Project>> {
     let mut projects = Vec::new();
-    let mut bw_path = io::get_bw_dir()?;
-    bw_path.push("frameworks/*/*/config.toml");
+    let mut bw_path = io::frameworks_dir()?;
+    bw_path.push("*/*/config.toml");
     for path in glob(bw_path.to_str().unwrap()).unwrap() {
-        let path_buf: &PathBuf = &path.unwrap();
-        let project_name = config::get_project_name_by_config_file(&path_buf)?;
-        let framework = config::get_framework_by_config_file(&path_buf)?;
+        let mut project = Project::from_config_file(&path.unwrap())?;
         let mut tests = Vec::new();
-        let language = config::get_language_by_config_file(&framework, &path_buf)?;
-        for mut test in config::get_test_implementations_by_config_file(&path_buf)? {
+        for mut test in std::mem::take(&mut project.tests) {
+            if exclude_broken && test.is_tagged_broken() {
+                continue;
+            }
             test.specify_test_type(test_type);
             if let Some(name) = &test_name {
                 if test.get_name() == *name {
@@ -103,12 +103,8 @@ pub fn list_projects_by_test_name(
             }
         }
         if !tests.is_empty() {
-            projects.push(Project {
-                name: project_name,
-                framework,
-                tests,
-                language,
-            });
+            project.tests = tests;
+            projects.push(project);
         }
     }
 
@@ -121,34 +117,22 @@ pub fn list_projects_by_language_name(
     test_type: Option<&str>,
 ) -> ToolsetResult<Vec<Project>> {
     let mut projects = Vec::new();
-    let mut bw_path = io::get_bw_dir()?;
-    bw_path.push("frameworks/*/*/config.toml");
+    let mut bw_path = io::frameworks_dir()?;
+    bw_path.push("*/*/config.toml");
     for path in glob(bw_path.to_str().unwrap()).unwrap() {
         let path_buf: &PathBuf = &path.unwrap();
-        let project_name = config::get_project_name_by_config_file(&path_buf)?;
-        let framework = config::get_framework_by_config_file(&path_buf)?;
-        let mut tests = Vec::new();
-        let language = config::get_language_by_config_file(&framework, &path_buf)?;
-        if language_name.to_lowercase() == language.to_lowercase() {
-            for mut test in config::get_test_implementations_by_config_file(&path_buf)? {
-                if test.tags.is_none()
-                    || !test
-                        .tags
-                        .as_ref()
-                        .unwrap()
-                        .contains(&TAG_BROKEN.to_string())
-                {
+        let mut project = Project::from_config_file(path_buf)?;
+        if language_name.to_lowercase() == project.language.to_lowercase() {
+            let mut tests = Vec::new();
+            for mut test in std::mem::take(&mut project.tests) {
+                if !test.is_tagged_broken() {
                     test.specify_test_type(test_type);
                     tests.push(test);
                 }
             }
             if !tests.is_empty() {
-                projects.push(Project {
-                    name: project_name,
-                    framework,
-                    tests,
-                    language,
-                });
+                project.tests = tests;
+                projects.push(project);
             }
         }
     }
@@ -165,33 +149,20 @@ pub fn list_projects_by_dir_name(
     test_type: Option<&str>,
 ) -> ToolsetResult<Vec<Project>> {
     let mut projects = Vec::new();
-    let mut bw_path = io::get_bw_dir()?;
-    bw_path.push(&format!("frameworks/{}/config.toml", dir_name));
+    let mut bw_path = io::frameworks_dir()?;
+    bw_path.push(&format!("{}/config.toml", dir_name));
     for path in glob(bw_path.to_str().unwrap()).unwrap() {
-        let path_buf: &PathBuf = &path.unwrap();
-        let project_name = config::get_project_name_by_config_file(&path_buf)?;
-        let framework = config::get_framework_by_config_file(&path_buf)?;
+        let mut project = Project::from_config_file(&path.unwrap())?;
         let mut tests = Vec::new();
-        let language = config::get_language_by_config_file(&framework, &path_buf)?;
-        for mut test in config::get_test_implementations_by_config_file(&path_buf)? {
-            if test.tags.is_none()
-                || !test
-                    .tags
-                    .as_ref()
-                    .unwrap()
-                    .contains(&TAG_BROKEN.to_string())
-            {
+        for mut test in std::mem::take(&mut project.tests) {
+            if !test.is_tagged_broken() {
                 test.specify_test_type(test_type);
                 tests.push(test);
             }
         }
         if !tests.is_empty() {
-            projects.push(Project {
-                name: project_name,
-                framework,
-                tests,
-                language,
-            });
+            project.tests = tests;
+            projects.push(project);
         }
     }
 
@@ -200,7 +171,39 @@ pub fn list_projects_by_dir_name(
 
 /// Convenience function for calling `metadata::list_projects_by_test_name(None)`.
 pub fn list_all_projects() -> ToolsetResult<Vec<Project>> {
-    list_projects_by_test_name(None, None)
+    list_projects_by_test_name(None, None, false)
+}
+
+/// Lists all projects like `list_all_projects`, additionally checking that
+/// each test's Dockerfile exists within the project's Docker build context
+/// (see `Test::validate_dockerfile_reference`). Problems found, including
+/// config files which fail to parse (e.g. a `NonUtf8ConfigError`), are
+/// returned alongside the projects as warnings rather than failing discovery.
+pub fn discover_all_validated() -> ToolsetResult<(Vec<Project>, Vec<ToolsetError>)> {
+    let mut projects = Vec::new();
+    let mut warnings = Vec::new();
+    let mut bw_path = io::frameworks_dir()?;
+    bw_path.push("*/*/config.toml");
+    for path in glob(bw_path.to_str().unwrap()).unwrap() {
+        match Project::from_config_file(&path.unwrap()) {
+            Ok(project) if !project.tests.is_empty() => projects.push(project),
+            Ok(_) => {}
+            Err(e) => warnings.push(e),
+        }
+    }
+    for project in &projects {
+        if let Err(e) = project.docker_context_path() {
+            warnings.push(e);
+            continue;
+        }
+        for test in &project.tests {
+            if let Err(e) = test.validate_dockerfile_reference(project) {
+                warnings.push(e);
+            }
+        }
+    }
+
+    Ok((projects, warnings))
 }
 
 /// Helper method to get the tests to run, specified or not.
@@ -213,6 +216,7 @@ pub fn list_projects_to_run(matches: &ArgMatches) -> Vec<Project> {
             match list_projects_by_test_name(
                 Some(String::from(test_name)),
                 matches.value_of(options::args::TYPES),
+                false,
             ) {
                 Ok(mut projects_found) => projects.append(&mut projects_found),
                 Err(e) => logger
@@ -316,9 +320,15 @@ fn get_test_implementations_by_path(path: &PathBuf) -> ToolsetResult<Vec<Test>>
 
 #[cfg(test)]
 mod tests {
+    use crate::config::{Named, Test};
+    use crate::error::ToolsetError::{
+        DockerfileNotFoundError, FrameworksDirMissingError, NonUtf8ConfigError,
+    };
+    use crate::io;
+    use crate::io::testing::temp_project_dir;
     use crate::metadata::{
-        list_all_frameworks, list_all_projects, list_all_tests, list_tests_by_tag,
-        list_tests_for_framework,
+        discover_all_validated, list_all_frameworks, list_all_projects, list_all_tests,
+        list_projects_by_test_name, list_tests_by_tag, list_tests_for_framework,
     };
 
     #[test]
@@ -363,4 +373,149 @@ mod tests {
             ),
         };
     }
+
+    #[test]
+    fn it_can_exclude_broken_tests_by_test_name() {
+        match list_projects_by_test_name(Some("gemini-mysql".to_string()), None, false) {
+            Ok(projects) => assert_eq!(projects.len(), 1),
+            Err(e) => panic!(
+                "metadata::list_projects_by_test_name failed. error: {:?}",
+                e
+            ),
+        };
+        match list_projects_by_test_name(Some("gemini-mysql".to_string()), None, true) {
+            Ok(projects) => assert!(projects.is_empty()),
+            Err(e) => panic!(
+                "metadata::list_projects_by_test_name failed. error: {:?}",
+                e
+            ),
+        };
+    }
+
+    #[test]
+    fn it_reports_a_missing_frameworks_dir() {
+        let mut bw_dir = std::env::temp_dir();
+        bw_dir.push(format!(
+            "bw_plugins_no_frameworks_{}",
+            uuid::Uuid::from_u128(rand::random())
+        ));
+        std::fs::create_dir_all(&bw_dir).unwrap();
+        io::set_bw_dir(Some(bw_dir.clone()));
+
+        match list_all_frameworks() {
+            Err(FrameworksDirMissingError(_)) => {}
+            other => panic!("expected FrameworksDirMissingError, got: {:?}", other),
+        }
+
+        io::set_bw_dir(None);
+        std::fs::remove_dir_all(bw_dir).unwrap();
+    }
+
+    #[test]
+    fn it_warns_about_a_missing_dockerfile() {
+        let (bw_dir, _guard) = temp_project_dir("Java", "gemini");
+        let project_dir = bw_dir.join("frameworks/Java/gemini");
+        std::fs::write(project_dir.join("gemini.dockerfile"), "FROM scratch").unwrap();
+        std::fs::write(
+            project_dir.join("config.toml"),
+            r#"
+[framework]
+name = "Gemini"
+
+[main]
+urls.json = "/json"
+approach = "Realistic"
+classification = "Fullstack"
+platform = "Servlet"
+webserver = "Resin"
+os = "Linux"
+versus = "servlet"
+dockerfile = "gemini.dockerfile"
+
+[postgres]
+urls.db = "/db"
+approach = "Realistic"
+classification = "Fullstack"
+database = "Postgres"
+platform = "Servlet"
+webserver = "Resin"
+os = "Linux"
+versus = "servlet"
+dockerfile = "missing.dockerfile"
+"#,
+        )
+        .unwrap();
+        io::set_bw_dir(Some(bw_dir.clone()));
+
+        match discover_all_validated() {
+            Ok((projects, warnings)) => {
+                assert_eq!(projects.len(), 1);
+                assert_eq!(warnings.len(), 1);
+                match &warnings[0] {
+                    DockerfileNotFoundError(test, _) => assert_eq!(test, "gemini-postgres"),
+                    other => panic!("expected DockerfileNotFoundError, got: {:?}", other),
+                }
+            }
+            Err(e) => panic!("metadata::discover_all_validated failed. error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn it_reports_a_non_utf8_config_and_keeps_discovering() {
+        let (bw_dir, _guard) = temp_project_dir("Java", "gemini");
+        let broken_dir = bw_dir.join("frameworks/Rust/actix");
+        std::fs::create_dir_all(&broken_dir).unwrap();
+        std::fs::write(
+            broken_dir.join("config.toml"),
+            b"[framework]\nname = \"\xff\xfe\"\n",
+        )
+        .unwrap();
+        io::set_bw_dir(Some(bw_dir));
+
+        match discover_all_validated() {
+            Ok((projects, warnings)) => {
+                assert_eq!(projects.len(), 1);
+                assert_eq!(projects[0].name, "gemini");
+                assert_eq!(warnings.len(), 1);
+                match &warnings[0] {
+                    NonUtf8ConfigError(path) => assert!(path.ends_with("Rust/actix/config.toml")),
+                    other => panic!("expected NonUtf8ConfigError, got: {:?}", other),
+                }
+            }
+            Err(e) => panic!("metadata::discover_all_validated failed. error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn it_merges_variant_configs_into_one_project() {
+        let (bw_dir, _guard) = temp_project_dir("Java", "gemini");
+        std::fs::write(
+            bw_dir.join("frameworks/Java/gemini/config.postgres.toml"),
+            r#"
+[framework]
+name = "gemini"
+
+[postgres]
+urls.db = "/db"
+approach = "Realistic"
+classification = "Fullstack"
+database = "Postgres"
+platform = "None"
+webserver = "None"
+os = "Linux"
+versus = ""
+"#,
+        )
+        .unwrap();
+        io::set_bw_dir(Some(bw_dir));
+
+        match list_all_projects() {
+            Ok(projects) => {
+                assert_eq!(projects.len(), 1);
+                let names: Vec<String> = projects[0].tests.iter().map(Test::get_name).collect();
+                assert_eq!(names, vec!["gemini", "gemini-postgres"]);
+            }
+            Err(e) => panic!("metadata::list_all_projects failed. error: {:?}", e),
+        }
+    }
 }